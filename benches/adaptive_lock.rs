@@ -0,0 +1,332 @@
+// Adaptive spin lock for ultra-short critical sections (a single pointer
+// or counter update held for a handful of instructions), where a full
+// OS-backed mutex is overkill. Benchmarks an "ultra-short critical
+// section" scenario (increment-and-return, zero other work) comparing
+// the adaptive lock against parking_lot::Mutex and std::sync::Mutex,
+// since mutex_comparison's benchmark body does enough work per iteration
+// to hide the difference this primitive targets.
+//
+// Run with: cargo run --release --bin adaptive_lock_bench
+
+use parking_lot_core::{ParkToken, UnparkToken};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u64 = 10_000_000;
+const THREAD_COUNTS: &[usize] = &[2, 4, 8, 16];
+
+/// Cap on the spin count of one backoff round: rounds double from 1 while
+/// they stay within it (1 to 64 at 100, 127 spins in all) before falling
+/// back to parking. Chosen so an
+/// uncontended acquire never parks, while a short hand-off between cores
+/// resolves within a handful of spin iterations rather than paying a
+/// syscall.
+const MAX_SPINS: u32 = 100;
+
+const LOCKED: u8 = 0b01;
+const PARKED: u8 = 0b10;
+
+/// Adaptive lock: an uncontended acquire is a single `AtomicU8` CAS; on
+/// contention it spins with exponential backoff (each round at most
+/// `MAX_SPINS` spins, relaxing the CPU each spin via `std::hint::spin_loop`)
+/// before falling back to `parking_lot_core::park`/`unpark_one` so a
+/// thread waiting behind a genuinely long hold is descheduled instead of
+/// burning CPU indefinitely. This mirrors how `parking_lot`'s own word-
+/// based `Mutex` is built on the same primitive.
+struct AdaptiveLock<T> {
+    state: AtomicU8,
+    value: std::cell::UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for AdaptiveLock<T> {}
+
+struct AdaptiveGuard<'a, T> {
+    lock: &'a AdaptiveLock<T>,
+}
+
+impl<'a, T> std::ops::Deref for AdaptiveGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for AdaptiveGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<'a, T> Drop for AdaptiveGuard<'a, T> {
+    fn drop(&mut self) {
+        self.lock.unlock();
+    }
+}
+
+impl<T> AdaptiveLock<T> {
+    fn new(value: T) -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            value: std::cell::UnsafeCell::new(value),
+        }
+    }
+
+    fn park_token_key(&self) -> usize {
+        &self.state as *const AtomicU8 as usize
+    }
+
+    fn lock(&self) -> AdaptiveGuard<'_, T> {
+        // Fast, uncontended path: a single CAS.
+        if self
+            .state
+            .compare_exchange(0, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            return AdaptiveGuard { lock: self };
+        }
+
+        self.lock_slow();
+        AdaptiveGuard { lock: self }
+    }
+
+    #[cold]
+    fn lock_slow(&self) {
+        // Contended: bounded exponential backoff with a CPU relax hint.
+        let mut spins: u32 = 1;
+        while spins <= MAX_SPINS {
+            for _ in 0..spins {
+                std::hint::spin_loop();
+            }
+            let state = self.state.load(Ordering::Relaxed);
+            if state & LOCKED == 0
+                && self
+                    .state
+                    .compare_exchange_weak(
+                        state,
+                        state | LOCKED,
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                return;
+            }
+            spins *= 2;
+        }
+
+        // Still contended after spinning: park via `parking_lot_core`
+        // rather than burn CPU indefinitely.
+        loop {
+            let state = self.state.load(Ordering::Relaxed);
+            if state & LOCKED == 0 {
+                if self
+                    .state
+                    .compare_exchange_weak(
+                        state,
+                        state | LOCKED,
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+                {
+                    return;
+                }
+                continue;
+            }
+
+            // Mark that a thread is about to park so the unlocking thread
+            // knows it needs to call `unpark_one`.
+            if state & PARKED == 0
+                && self
+                    .state
+                    .compare_exchange_weak(
+                        state,
+                        state | PARKED,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    )
+                    .is_err()
+            {
+                continue;
+            }
+
+            let key = self.park_token_key();
+            // Re-check under the parking bucket lock that the lock is
+            // still held and still marked PARKED, so a release that raced
+            // ahead of us doesn't cause a lost wakeup.
+            let validate = || self.state.load(Ordering::Relaxed) == LOCKED | PARKED;
+            unsafe {
+                parking_lot_core::park(key, validate, || {}, |_, _| {}, ParkToken(0), None);
+            }
+        }
+    }
+
+    fn unlock(&self) {
+        // Fast path: atomically clear LOCKED only if PARKED was never set,
+        // so this doesn't race with a concurrent `lock_slow` setting the
+        // bit after we've read it. If it fails, someone is (or was about
+        // to be) parked and we must go through `unpark_one` to find out
+        // whether they're still waiting.
+        if self
+            .state
+            .compare_exchange(LOCKED, 0, Ordering::Release, Ordering::Relaxed)
+            .is_ok()
+        {
+            return;
+        }
+        self.unlock_slow();
+    }
+
+    #[cold]
+    fn unlock_slow(&self) {
+        let key = self.park_token_key();
+        unsafe {
+            parking_lot_core::unpark_one(key, |result| {
+                // `unpark_one`'s callback runs under the bucket lock, so
+                // this is synchronized with any other thread still parking
+                // on `key`. Leave PARKED set if `have_more_threads` says
+                // another waiter remains, otherwise a woken thread's
+                // re-acquire CAS would read a stale `0` and silently drop
+                // the bit, stranding that waiter forever (lost wakeup).
+                self.state.store(
+                    if result.have_more_threads { PARKED } else { 0 },
+                    Ordering::Release,
+                );
+                UnparkToken(0)
+            });
+        }
+    }
+}
+
+fn bench_adaptive_ultra_short(threads: usize) -> Duration {
+    let lock = Arc::new(AdaptiveLock::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = lock.lock();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn bench_parking_lot_ultra_short(threads: usize) -> Duration {
+    let mutex = Arc::new(parking_lot::Mutex::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let mutex = Arc::clone(&mutex);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = mutex.lock();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn bench_std_ultra_short(threads: usize) -> Duration {
+    let mutex = Arc::new(std::sync::Mutex::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let mutex = Arc::clone(&mutex);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = mutex.lock().unwrap();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn format_results(name: &str, adaptive: Duration, parking_lot: Duration, std: Duration) {
+    let adaptive_ns = adaptive.as_nanos() as f64 / ITERATIONS as f64;
+    let pl_ns = parking_lot.as_nanos() as f64 / ITERATIONS as f64;
+    let std_ns = std.as_nanos() as f64 / ITERATIONS as f64;
+
+    println!("\n{name}:");
+    println!("  AdaptiveLock:         {adaptive_ns:.2} ns/op");
+    println!("  parking_lot::Mutex:   {pl_ns:.2} ns/op");
+    println!("  std::sync::Mutex:     {std_ns:.2} ns/op");
+}
+
+fn main() {
+    println!("Adaptive Lock Benchmark (ultra-short critical section)");
+    println!("========================================================");
+    println!("Iterations: {ITERATIONS}");
+    println!("Scenario: increment-and-return under the lock, zero other work");
+
+    println!("\nRunning ultra-short critical section benchmarks...");
+    for &threads in THREAD_COUNTS {
+        let adaptive = bench_adaptive_ultra_short(threads);
+        let pl = bench_parking_lot_ultra_short(threads);
+        let std = bench_std_ultra_short(threads);
+        format_results(&format!("{threads} threads"), adaptive, pl, std);
+    }
+
+    println!("\n=== Summary ===");
+    println!("AdaptiveLock advantages:");
+    println!("  - Uncontended acquire is a single CAS, no syscall path at all");
+    println!("  - Bounded spin-with-backoff absorbs brief contention without parking");
+    println!("  - Falls back to parking only once spinning has clearly stopped paying off");
+    println!("  - Best suited to single-pointer/counter updates, not general critical sections");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enforces_mutual_exclusion_under_heavy_contention() {
+        // Enough threads and increments to blow well past MAX_SPINS and
+        // exercise the parking_lot_core park/unpark slow path.
+        const THREADS: usize = 16;
+        const INCREMENTS: u64 = 20_000;
+
+        let lock = Arc::new(AdaptiveLock::new(0u64));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let lock = Arc::clone(&lock);
+                thread::spawn(move || {
+                    for _ in 0..INCREMENTS {
+                        let mut guard = lock.lock();
+                        *guard += 1;
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*lock.lock(), THREADS as u64 * INCREMENTS);
+    }
+}
@@ -72,6 +72,127 @@ fn bench_parking_lot_contended(threads: usize) -> Duration {
     start.elapsed()
 }
 
+/// `parking_lot::FairMutex` guarantees FIFO hand-off between waiters (no
+/// thread can be starved by a releasing thread's own re-acquisition), at
+/// the cost of throughput relative to the default eager/unfair `Mutex`.
+fn bench_fair_mutex_contended(threads: usize) -> Duration {
+    let mutex = Arc::new(parking_lot::FairMutex::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let mutex = Arc::clone(&mutex);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = mutex.lock();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+/// Lock wrapper for cross-thread shared state (e.g. an `Isolate` accessed
+/// from multiple background threads via a `Locker`). `lock()` keeps the
+/// fast, unfair `parking_lot::Mutex` path for the common low-contention
+/// case; `lock_fair()` hands the lock back in FIFO order on release so a
+/// thread holding it for a long-running operation cannot repeatedly
+/// re-acquire ahead of a waiter that has been parked the longest.
+struct SharedLock<T> {
+    inner: parking_lot::Mutex<T>,
+}
+
+struct FairGuard<'a, T>(Option<parking_lot::MutexGuard<'a, T>>);
+
+impl<'a, T> std::ops::Deref for FairGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for FairGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut().unwrap()
+    }
+}
+
+impl<'a, T> Drop for FairGuard<'a, T> {
+    fn drop(&mut self) {
+        if let Some(guard) = self.0.take() {
+            parking_lot::MutexGuard::unlock_fair(guard);
+        }
+    }
+}
+
+impl<T> SharedLock<T> {
+    fn new(value: T) -> Self {
+        Self {
+            inner: parking_lot::Mutex::new(value),
+        }
+    }
+
+    /// Fast, unfair acquire: suitable for short, low-contention sections.
+    fn lock(&self) -> parking_lot::MutexGuard<'_, T> {
+        self.inner.lock()
+    }
+
+    /// FIFO acquire/release: suitable for long-held cross-thread sections
+    /// where starving a background waiter would be unacceptable.
+    fn lock_fair(&self) -> FairGuard<'_, T> {
+        FairGuard(Some(self.inner.lock()))
+    }
+}
+
+fn bench_shared_lock_contended(threads: usize) -> Duration {
+    let lock = Arc::new(SharedLock::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = lock.lock();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn bench_shared_lock_fair_contended(threads: usize) -> Duration {
+    let lock = Arc::new(SharedLock::new(0u64));
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..(ITERATIONS / threads as u64) {
+                    let mut guard = lock.lock_fair();
+                    *guard += 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    start.elapsed()
+}
+
 fn format_results(name: &str, std_time: Duration, pl_time: Duration) {
     let std_ns = std_time.as_nanos() as f64 / ITERATIONS as f64;
     let pl_ns = pl_time.as_nanos() as f64 / ITERATIONS as f64;
@@ -83,6 +204,31 @@ fn format_results(name: &str, std_time: Duration, pl_time: Duration) {
     println!("  Speedup:             {speedup:.2}x");
 }
 
+fn format_results_with_fair(
+    name: &str,
+    std_time: Duration,
+    pl_time: Duration,
+    fair_time: Duration,
+) {
+    let std_ns = std_time.as_nanos() as f64 / ITERATIONS as f64;
+    let pl_ns = pl_time.as_nanos() as f64 / ITERATIONS as f64;
+    let fair_ns = fair_time.as_nanos() as f64 / ITERATIONS as f64;
+
+    println!("\n{name}:");
+    println!("  std::sync::Mutex:    {std_ns:.2} ns/op");
+    println!("  parking_lot::Mutex:  {pl_ns:.2} ns/op");
+    println!("  parking_lot::FairMutex: {fair_ns:.2} ns/op");
+}
+
+fn format_results_shared_lock(name: &str, unfair_time: Duration, fair_time: Duration) {
+    let unfair_ns = unfair_time.as_nanos() as f64 / ITERATIONS as f64;
+    let fair_ns = fair_time.as_nanos() as f64 / ITERATIONS as f64;
+
+    println!("\n{name}:");
+    println!("  SharedLock::lock():      {unfair_ns:.2} ns/op");
+    println!("  SharedLock::lock_fair(): {fair_ns:.2} ns/op");
+}
+
 fn main() {
     println!("Mutex Benchmark Comparison");
     println!("==========================");
@@ -90,10 +236,22 @@ fn main() {
 
     // Size comparison (important: empty mutex size)
     println!("\nSize comparison:");
-    println!("  std::sync::Mutex<()>:    {} bytes", std::mem::size_of::<std::sync::Mutex<()>>());
-    println!("  parking_lot::Mutex<()>:  {} bytes", std::mem::size_of::<parking_lot::Mutex<()>>());
-    println!("  std::sync::Mutex<u64>:   {} bytes", std::mem::size_of::<std::sync::Mutex<u64>>());
-    println!("  parking_lot::Mutex<u64>: {} bytes", std::mem::size_of::<parking_lot::Mutex<u64>>());
+    println!(
+        "  std::sync::Mutex<()>:    {} bytes",
+        std::mem::size_of::<std::sync::Mutex<()>>()
+    );
+    println!(
+        "  parking_lot::Mutex<()>:  {} bytes",
+        std::mem::size_of::<parking_lot::Mutex<()>>()
+    );
+    println!(
+        "  std::sync::Mutex<u64>:   {} bytes",
+        std::mem::size_of::<std::sync::Mutex<u64>>()
+    );
+    println!(
+        "  parking_lot::Mutex<u64>: {} bytes",
+        std::mem::size_of::<parking_lot::Mutex<u64>>()
+    );
 
     // Warmup
     let _ = bench_std_mutex_uncontended();
@@ -102,15 +260,42 @@ fn main() {
     // Uncontended benchmarks (run 3 times, take best)
     println!("\nRunning uncontended benchmarks (best of 3)...");
     let std_uncontended = (0..3).map(|_| bench_std_mutex_uncontended()).min().unwrap();
-    let pl_uncontended = (0..3).map(|_| bench_parking_lot_uncontended()).min().unwrap();
-    format_results("Uncontended (single thread)", std_uncontended, pl_uncontended);
+    let pl_uncontended = (0..3)
+        .map(|_| bench_parking_lot_uncontended())
+        .min()
+        .unwrap();
+    format_results(
+        "Uncontended (single thread)",
+        std_uncontended,
+        pl_uncontended,
+    );
 
     // Contended benchmarks with different thread counts
     println!("\nRunning contended benchmarks...");
     for &threads in THREAD_COUNTS {
         let std_contended = bench_std_mutex_contended(threads);
         let pl_contended = bench_parking_lot_contended(threads);
-        format_results(&format!("Contended ({threads} threads)"), std_contended, pl_contended);
+        let fair_contended = bench_fair_mutex_contended(threads);
+        format_results_with_fair(
+            &format!("Contended ({threads} threads)"),
+            std_contended,
+            pl_contended,
+            fair_contended,
+        );
+    }
+
+    // SharedLock is the wrapper rusty_v8 would actually expose at lock
+    // sites: lock() keeps the fast unfair path, lock_fair() opts a
+    // long-held cross-thread section into FIFO hand-off.
+    println!("\nRunning SharedLock contended benchmarks...");
+    for &threads in THREAD_COUNTS {
+        let unfair_contended = bench_shared_lock_contended(threads);
+        let fair_contended = bench_shared_lock_fair_contended(threads);
+        format_results_shared_lock(
+            &format!("SharedLock contended ({threads} threads)"),
+            unfair_contended,
+            fair_contended,
+        );
     }
 
     println!("\n=== Summary ===");
@@ -122,4 +307,59 @@ fn main() {
     println!();
     println!("Note: std::sync::Mutex may perform better under very high contention");
     println!("      (8+ threads), but rusty_v8 usage patterns are low contention.");
+    println!();
+    println!("parking_lot::FairMutex trades throughput for FIFO fairness: use");
+    println!("SharedLock::lock_fair() on the rare long-held cross-thread section");
+    println!("where starving a background waiter would be worse than the overhead.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn lock_fair_hands_off_in_park_order() {
+        const WAITERS: usize = 8;
+
+        let lock = Arc::new(SharedLock::new(0u64));
+        let guard = lock.lock_fair();
+
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let parked = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..WAITERS)
+            .map(|i| {
+                let lock = Arc::clone(&lock);
+                let order = Arc::clone(&order);
+                let parked = Arc::clone(&parked);
+                let parked_waiter = Arc::clone(&parked);
+                // Spawned in order and given time to actually block on the
+                // held lock before the next one starts, so park order
+                // matches spawn order and the FIFO guarantee is actually
+                // under test rather than incidental scheduling luck.
+                thread::sleep(Duration::from_millis(5));
+                let handle = thread::spawn(move || {
+                    parked_waiter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let _guard = lock.lock_fair();
+                    order.lock().unwrap().push(i);
+                });
+                while parked.load(std::sync::atomic::Ordering::SeqCst) <= i {
+                    thread::yield_now();
+                }
+                handle
+            })
+            .collect();
+
+        // Give the last waiter a moment to actually reach the park call
+        // (incrementing `parked` happens just before it) before releasing.
+        thread::sleep(Duration::from_millis(20));
+        drop(guard);
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), (0..WAITERS).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,217 @@
+// Thin panic-safety wrapper over parking_lot's (non-poisoning) locks, plus
+// a benchmark of its uncontended acquire cost against std::sync::Mutex's
+// built-in poisoning check.
+//
+// parking_lot deliberately drops poisoning for speed and a cleaner API,
+// but some embedders rely on poisoning to turn "a thread panicked
+// mid-mutation of V8 state" into a hard failure instead of silently
+// continuing on corrupt data. `TaintedLock` restores that guarantee as an
+// opt-in: a guard dropped during unwinding marks the lock tainted, and
+// the next acquisition either errors or aborts, per `PoisonPolicy`.
+//
+// Run with: cargo run --release --bin poison_guard_bench
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u64 = 10_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PoisonPolicy {
+    /// Abort the process on the next acquisition after a panic.
+    Abort,
+    /// Return an error on the next acquisition after a panic.
+    ReturnError,
+}
+
+#[derive(Debug)]
+struct Tainted;
+
+impl std::fmt::Display for Tainted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("lock tainted by a panic while held")
+    }
+}
+
+impl std::error::Error for Tainted {}
+
+struct TaintedLock<T> {
+    inner: parking_lot::Mutex<T>,
+    tainted: AtomicBool,
+    policy: PoisonPolicy,
+}
+
+struct TaintedGuard<'a, T> {
+    guard: parking_lot::MutexGuard<'a, T>,
+    tainted: &'a AtomicBool,
+}
+
+impl<'a, T> std::ops::Deref for TaintedGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for TaintedGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<'a, T> Drop for TaintedGuard<'a, T> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.tainted.store(true, Ordering::Release);
+        }
+    }
+}
+
+impl<T> TaintedLock<T> {
+    fn new(policy: PoisonPolicy, value: T) -> Self {
+        Self {
+            inner: parking_lot::Mutex::new(value),
+            tainted: AtomicBool::new(false),
+            policy,
+        }
+    }
+
+    fn lock(&self) -> Result<TaintedGuard<'_, T>, Tainted> {
+        if self.tainted.load(Ordering::Acquire) {
+            match self.policy {
+                PoisonPolicy::Abort => std::process::abort(),
+                PoisonPolicy::ReturnError => return Err(Tainted),
+            }
+        }
+        Ok(TaintedGuard {
+            guard: self.inner.lock(),
+            tainted: &self.tainted,
+        })
+    }
+}
+
+fn bench_std_mutex_uncontended() -> Duration {
+    let mutex = std::sync::Mutex::new(0u64);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut guard = mutex.lock().unwrap();
+        *guard += 1;
+    }
+    start.elapsed()
+}
+
+fn bench_tainted_lock_uncontended(policy: PoisonPolicy) -> Duration {
+    let lock = TaintedLock::new(policy, 0u64);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut guard = lock.lock().unwrap();
+        *guard += 1;
+    }
+    start.elapsed()
+}
+
+fn format_results(name: &str, std_time: Duration, tainted_time: Duration) {
+    let std_ns = std_time.as_nanos() as f64 / ITERATIONS as f64;
+    let tainted_ns = tainted_time.as_nanos() as f64 / ITERATIONS as f64;
+    let speedup = std_time.as_nanos() as f64 / tainted_time.as_nanos() as f64;
+
+    println!("\n{name}:");
+    println!("  std::sync::Mutex (poisoning): {std_ns:.2} ns/op");
+    println!("  TaintedLock (no-panic path):  {tainted_ns:.2} ns/op");
+    println!("  Speedup:                      {speedup:.2}x");
+}
+
+fn main() {
+    println!("Poison-Detection Guard Benchmark");
+    println!("=================================");
+    println!("Iterations: {ITERATIONS}");
+
+    println!("\nRunning uncontended benchmarks...");
+    let std_time = bench_std_mutex_uncontended();
+    let tainted_return_error_time = bench_tainted_lock_uncontended(PoisonPolicy::ReturnError);
+    format_results(
+        "Uncontended (single thread, no panics, ReturnError policy)",
+        std_time,
+        tainted_return_error_time,
+    );
+    let tainted_abort_time = bench_tainted_lock_uncontended(PoisonPolicy::Abort);
+    format_results(
+        "Uncontended (single thread, no panics, Abort policy)",
+        std_time,
+        tainted_abort_time,
+    );
+
+    println!("\n=== Summary ===");
+    println!("TaintedLock gives back std's panic-safety guarantee (a panic while");
+    println!("mutating shared state becomes a hard failure on the next acquisition)");
+    println!("at the cost of one flag load per acquire and one thread::panicking()");
+    println!("check per release in the common no-panic case.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::AssertUnwindSafe;
+
+    /// Env var the child process checks for to run just the abort scenario
+    /// instead of the full test binary (see `abort_policy_aborts_process`).
+    const ABORT_CHILD_ENV: &str = "POISON_GUARD_ABORT_CHILD";
+
+    #[test]
+    fn return_error_policy_taints_on_panic() {
+        let lock = std::sync::Arc::new(TaintedLock::new(PoisonPolicy::ReturnError, 0u64));
+        let lock2 = std::sync::Arc::clone(&lock);
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard = lock2.lock().unwrap();
+            panic!("simulated corruption mid-mutation");
+        }));
+        assert!(result.is_err());
+
+        assert!(matches!(lock.lock(), Err(Tainted)));
+        // The taint is sticky: it keeps being observed on later attempts too.
+        assert!(matches!(lock.lock(), Err(Tainted)));
+    }
+
+    #[test]
+    fn untainted_lock_is_unaffected_by_unrelated_panics() {
+        let lock = TaintedLock::new(PoisonPolicy::ReturnError, 0u64);
+        // A panic while no guard of this lock is held must not taint it.
+        let result = std::panic::catch_unwind(|| panic!("unrelated panic"));
+        assert!(result.is_err());
+        {
+            let mut guard = lock.lock().unwrap();
+            *guard += 1;
+        }
+        assert_eq!(*lock.lock().unwrap(), 1);
+    }
+
+    // `PoisonPolicy::Abort` calls `std::process::abort()`, which would take
+    // down the whole test binary, so this test re-execs itself as a child
+    // process to run just the abort scenario and asserts the child died.
+    #[test]
+    fn abort_policy_aborts_process() {
+        if std::env::var_os(ABORT_CHILD_ENV).is_some() {
+            let lock = TaintedLock::new(PoisonPolicy::Abort, 0u64);
+            let lock = std::sync::Arc::new(lock);
+            let lock2 = std::sync::Arc::clone(&lock);
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                let _guard = lock2.lock().unwrap();
+                panic!("simulated corruption mid-mutation");
+            }));
+            // Tainted: this acquisition must abort the process.
+            let _ = lock.lock();
+            unreachable!("PoisonPolicy::Abort failed to abort the process");
+        }
+
+        let exe = std::env::current_exe().expect("current test exe");
+        let status = std::process::Command::new(exe)
+            .arg("tests::abort_policy_aborts_process")
+            .arg("--exact")
+            .arg("--nocapture")
+            .env(ABORT_CHILD_ENV, "1")
+            .status()
+            .expect("failed to spawn child test process");
+        assert!(!status.success(), "child process should have aborted");
+    }
+}
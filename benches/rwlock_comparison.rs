@@ -0,0 +1,283 @@
+// Benchmark comparing std::sync::RwLock vs parking_lot::RwLock for
+// read-mostly workloads (external reference registries, handle/context
+// lookup tables), plus a demonstration of the upgradable-read pattern
+// used to avoid a read-unlock/write-relock race on "look up, insert only
+// if missing" access.
+//
+// Run with: cargo run --release --bin rwlock_bench
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u64 = 10_000_000;
+const THREAD_COUNTS: &[usize] = &[2, 4, 8, 16];
+
+fn bench_std_rwlock_read_heavy(readers: usize) -> Duration {
+    let lock = Arc::new(std::sync::RwLock::new(0u64));
+    let start = Instant::now();
+
+    let writer = {
+        let lock = Arc::clone(&lock);
+        thread::spawn(move || {
+            for _ in 0..(ITERATIONS / 100) {
+                let mut guard = lock.write().unwrap();
+                *guard = guard.wrapping_add(1);
+            }
+        })
+    };
+
+    let handles: Vec<_> = (0..readers)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                let mut sum = 0u64;
+                for _ in 0..(ITERATIONS / readers as u64) {
+                    let guard = lock.read().unwrap();
+                    sum = sum.wrapping_add(*guard);
+                }
+                sum
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join().unwrap();
+    }
+    writer.join().unwrap();
+    start.elapsed()
+}
+
+fn bench_parking_lot_rwlock_read_heavy(readers: usize) -> Duration {
+    let lock = Arc::new(parking_lot::RwLock::new(0u64));
+    let start = Instant::now();
+
+    let writer = {
+        let lock = Arc::clone(&lock);
+        thread::spawn(move || {
+            for _ in 0..(ITERATIONS / 100) {
+                let mut guard = lock.write();
+                *guard = guard.wrapping_add(1);
+            }
+        })
+    };
+
+    let handles: Vec<_> = (0..readers)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                let mut sum = 0u64;
+                for _ in 0..(ITERATIONS / readers as u64) {
+                    let guard = lock.read();
+                    sum = sum.wrapping_add(*guard);
+                }
+                sum
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join().unwrap();
+    }
+    writer.join().unwrap();
+    start.elapsed()
+}
+
+/// Read-mostly registry (models the external reference registry / handle
+/// lookup tables) backed by `parking_lot::RwLock`. `get_or_insert_with`
+/// takes an upgradable read lock, which coexists with plain `read()`
+/// holders but not with other upgradable readers or writers, so
+/// concurrent `get_or_insert_with` calls run one at a time. It only
+/// escalates to a write lock via `RwLockUpgradableReadGuard::upgrade`
+/// when the entry is actually missing, and the upgrade never releases
+/// the lock, so there is no window for another thread to race into
+/// between the lookup and the insert.
+struct Registry<K, V> {
+    entries: parking_lot::RwLock<HashMap<K, V>>,
+}
+
+impl<K: std::hash::Hash + Eq + Clone, V: Clone> Registry<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: parking_lot::RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn get_or_insert_with(&self, key: K, make: impl FnOnce() -> V) -> V {
+        let upgradable = self.entries.upgradable_read();
+        if let Some(value) = upgradable.get(&key) {
+            return value.clone();
+        }
+        let mut writable = parking_lot::RwLockUpgradableReadGuard::upgrade(upgradable);
+        writable.entry(key).or_insert_with(make).clone()
+    }
+}
+
+/// Drives `Registry::get_or_insert_with` with concurrent readers hitting
+/// already-populated keys (the common case) and a writer racing in a
+/// handful of fresh keys, mirroring the read-heavy benches above but
+/// exercising the upgradable-read path they don't touch.
+fn bench_registry_get_or_insert_with(readers: usize) -> Duration {
+    let registry = Arc::new(Registry::<u64, u64>::new());
+    for key in 0..1024u64 {
+        registry.get_or_insert_with(key, || key);
+    }
+    let start = Instant::now();
+
+    let writer = {
+        let registry = Arc::clone(&registry);
+        thread::spawn(move || {
+            for key in 0..(ITERATIONS / 100) {
+                registry.get_or_insert_with(1024 + (key % 1024), || key);
+            }
+        })
+    };
+
+    let handles: Vec<_> = (0..readers)
+        .map(|_| {
+            let registry = Arc::clone(&registry);
+            thread::spawn(move || {
+                let mut sum = 0u64;
+                for i in 0..(ITERATIONS / readers as u64) {
+                    sum = sum.wrapping_add(registry.get_or_insert_with(i % 1024, || i));
+                }
+                sum
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join().unwrap();
+    }
+    writer.join().unwrap();
+    start.elapsed()
+}
+
+fn format_results(name: &str, std_time: Duration, pl_time: Duration) {
+    let std_ns = std_time.as_nanos() as f64 / ITERATIONS as f64;
+    let pl_ns = pl_time.as_nanos() as f64 / ITERATIONS as f64;
+    let speedup = std_time.as_nanos() as f64 / pl_time.as_nanos() as f64;
+
+    println!("\n{name}:");
+    println!("  std::sync::RwLock:    {std_ns:.2} ns/op");
+    println!("  parking_lot::RwLock:  {pl_ns:.2} ns/op");
+    println!("  Speedup:              {speedup:.2}x");
+}
+
+fn format_registry_results(name: &str, time: Duration) {
+    let ns = time.as_nanos() as f64 / ITERATIONS as f64;
+    println!("\n{name}:");
+    println!("  Registry::get_or_insert_with: {ns:.2} ns/op");
+}
+
+fn main() {
+    println!("RwLock Benchmark Comparison (read-heavy)");
+    println!("=========================================");
+    println!("Iterations: {ITERATIONS} (per reader pool, plus a background writer)");
+
+    println!("\nSize comparison:");
+    println!(
+        "  std::sync::RwLock<()>:    {} bytes",
+        std::mem::size_of::<std::sync::RwLock<()>>()
+    );
+    println!(
+        "  parking_lot::RwLock<()>:  {} bytes",
+        std::mem::size_of::<parking_lot::RwLock<()>>()
+    );
+
+    println!("\nRunning read-heavy benchmarks...");
+    for &readers in THREAD_COUNTS {
+        let std_time = bench_std_rwlock_read_heavy(readers);
+        let pl_time = bench_parking_lot_rwlock_read_heavy(readers);
+        format_results(&format!("{readers} readers, 1 writer"), std_time, pl_time);
+    }
+
+    println!("\nRunning Registry (upgradable-read) benchmarks...");
+    for &readers in THREAD_COUNTS {
+        let time = bench_registry_get_or_insert_with(readers);
+        format_registry_results(&format!("{readers} readers, 1 writer"), time);
+    }
+
+    println!("\n=== Summary ===");
+    println!("parking_lot::RwLock advantages:");
+    println!("  - Smaller and faster under read-heavy contention");
+    println!("  - Upgradable-read guards avoid the unlock/relock race for");
+    println!("    \"look up, insert only if missing\" patterns (see Registry)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn get_or_insert_with_behaves_like_a_get_or_insert_cache() {
+        let registry: Registry<&'static str, u64> = Registry::new();
+        let first = registry.get_or_insert_with("answer", || 42);
+        let second = registry.get_or_insert_with("answer", || panic!("should not recompute"));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn concurrent_racers_on_a_missing_key_insert_exactly_once() {
+        const THREADS: usize = 16;
+
+        let registry = Arc::new(Registry::<&'static str, u64>::new());
+        let make_calls = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|i| {
+                let registry = Arc::clone(&registry);
+                let make_calls = Arc::clone(&make_calls);
+                thread::spawn(move || {
+                    registry.get_or_insert_with("shared", || {
+                        make_calls.fetch_add(1, Ordering::SeqCst);
+                        i as u64
+                    })
+                })
+            })
+            .collect();
+
+        let results: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        // Every racer must observe the same winning value...
+        let winner = results[0];
+        assert!(results.iter().all(|&value| value == winner));
+        // ...and `make` must have run exactly once: no two threads both
+        // upgraded past the upgradable-read check and inserted.
+        assert_eq!(make_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn concurrent_access_to_distinct_keys_does_not_corrupt_the_map() {
+        const THREADS: usize = 16;
+        const KEYS_PER_THREAD: usize = 200;
+
+        let registry = Arc::new(Registry::<usize, usize>::new());
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let registry = Arc::clone(&registry);
+                thread::spawn(move || {
+                    for k in 0..KEYS_PER_THREAD {
+                        let key = t * KEYS_PER_THREAD + k;
+                        let value = registry.get_or_insert_with(key, || key);
+                        assert_eq!(value, key);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for t in 0..THREADS {
+            for k in 0..KEYS_PER_THREAD {
+                let key = t * KEYS_PER_THREAD + k;
+                assert_eq!(registry.get_or_insert_with(key, || panic!("key went missing")), key);
+            }
+        }
+    }
+}
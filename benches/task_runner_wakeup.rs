@@ -0,0 +1,288 @@
+// Benchmark comparing post-to-wake latency of std::sync::Condvar vs
+// parking_lot::Condvar, backing a blocking interface for the background
+// task runner so an embedder thread can sleep until a task is posted or
+// a deadline elapses instead of busy-polling the task queue.
+//
+// Run with: cargo run --release --bin task_runner_wakeup_bench
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WAKEUPS: usize = 100_000;
+
+struct State<T> {
+    queue: VecDeque<T>,
+    /// Set by `wake()` to unblock a waiter even though no task was posted
+    /// (e.g. to tell an idle embedder thread to re-check shutdown state).
+    woken: bool,
+}
+
+/// Blocking task runner built on `parking_lot::Condvar`, which (unlike the
+/// std condvar) supports `wait_until`/`wait_for` against a deadline and
+/// reports whether the wait timed out, instead of requiring the caller to
+/// re-check a spurious-wakeup loop against `Instant::now()` by hand.
+struct TaskRunner<T> {
+    state: parking_lot::Mutex<State<T>>,
+    posted: parking_lot::Condvar,
+}
+
+impl<T> TaskRunner<T> {
+    fn new() -> Self {
+        Self {
+            state: parking_lot::Mutex::new(State {
+                queue: VecDeque::new(),
+                woken: false,
+            }),
+            posted: parking_lot::Condvar::new(),
+        }
+    }
+
+    /// Post a task and wake a single waiter (if any).
+    fn post(&self, task: T) {
+        self.state.lock().queue.push_back(task);
+        self.posted.notify_one();
+    }
+
+    /// Run every currently-queued task through `f` without blocking.
+    fn run_until_idle(&self, mut f: impl FnMut(T)) {
+        loop {
+            let task = self.state.lock().queue.pop_front();
+            match task {
+                Some(task) => f(task),
+                None => break,
+            }
+        }
+    }
+
+    /// Block until a task is available, `wake()` is called, or `timeout`
+    /// elapses (waits forever if `timeout` is `None`). Returns the task,
+    /// or `None` if unblocked by `wake()` or a timeout.
+    fn block_until_task(&self, timeout: Option<Duration>) -> Option<T> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut guard = self.state.lock();
+        loop {
+            if let Some(task) = guard.queue.pop_front() {
+                return Some(task);
+            }
+            if guard.woken {
+                guard.woken = false;
+                return None;
+            }
+            let timed_out = match deadline {
+                Some(deadline) => self.posted.wait_until(&mut guard, deadline).timed_out(),
+                None => {
+                    self.posted.wait(&mut guard);
+                    false
+                }
+            };
+            if timed_out {
+                return None;
+            }
+        }
+    }
+
+    /// Wake a single waiter without posting a task (e.g. for shutdown).
+    fn wake(&self) {
+        self.state.lock().woken = true;
+        self.posted.notify_one();
+    }
+}
+
+fn bench_std_condvar_wakeup() -> Duration {
+    let pair = Arc::new((std::sync::Mutex::new(false), std::sync::Condvar::new()));
+    let mut total = Duration::ZERO;
+
+    for _ in 0..WAKEUPS {
+        let pair2 = Arc::clone(&pair);
+        let waiter = thread::spawn(move || {
+            let (lock, cvar) = &*pair2;
+            let mut ready = lock.lock().unwrap();
+            while !*ready {
+                ready = cvar.wait(ready).unwrap();
+            }
+        });
+
+        // Give the waiter a chance to block before we post.
+        thread::yield_now();
+
+        let start = Instant::now();
+        {
+            let (lock, cvar) = &*pair;
+            let mut ready = lock.lock().unwrap();
+            *ready = true;
+            cvar.notify_one();
+        }
+        waiter.join().unwrap();
+        total += start.elapsed();
+
+        let (lock, _) = &*pair;
+        *lock.lock().unwrap() = false;
+    }
+
+    total
+}
+
+fn bench_parking_lot_condvar_wakeup() -> Duration {
+    let pair = Arc::new((parking_lot::Mutex::new(false), parking_lot::Condvar::new()));
+    let mut total = Duration::ZERO;
+
+    for _ in 0..WAKEUPS {
+        let pair2 = Arc::clone(&pair);
+        let waiter = thread::spawn(move || {
+            let (lock, cvar) = &*pair2;
+            let mut ready = lock.lock();
+            while !*ready {
+                cvar.wait(&mut ready);
+            }
+        });
+
+        thread::yield_now();
+
+        let start = Instant::now();
+        {
+            let (lock, cvar) = &*pair;
+            let mut ready = lock.lock();
+            *ready = true;
+            cvar.notify_one();
+        }
+        waiter.join().unwrap();
+        total += start.elapsed();
+
+        let (lock, _) = &*pair;
+        *lock.lock() = false;
+    }
+
+    total
+}
+
+fn bench_task_runner_wakeup() -> Duration {
+    let runner = Arc::new(TaskRunner::new());
+    let mut total = Duration::ZERO;
+
+    for _ in 0..WAKEUPS {
+        let runner2 = Arc::clone(&runner);
+        let waiter = thread::spawn(move || {
+            runner2.block_until_task(None);
+        });
+
+        // Give the waiter a chance to block before we post.
+        thread::yield_now();
+
+        let start = Instant::now();
+        runner.post(0u64);
+        waiter.join().unwrap();
+        total += start.elapsed();
+    }
+
+    total
+}
+
+fn format_results(name: &str, std_time: Duration, pl_time: Duration) {
+    let std_ns = std_time.as_nanos() as f64 / WAKEUPS as f64;
+    let pl_ns = pl_time.as_nanos() as f64 / WAKEUPS as f64;
+    let speedup = std_time.as_nanos() as f64 / pl_time.as_nanos() as f64;
+
+    println!("\n{name}:");
+    println!("  std::sync::Condvar:    {std_ns:.2} ns/wakeup");
+    println!("  parking_lot::Condvar:  {pl_ns:.2} ns/wakeup");
+    println!("  Speedup:               {speedup:.2}x");
+}
+
+fn main() {
+    println!("Condvar Wakeup-Latency Benchmark Comparison");
+    println!("============================================");
+    println!("Wakeups: {WAKEUPS}");
+
+    println!("\nRunning wakeup-latency benchmarks...");
+    let std_time = bench_std_condvar_wakeup();
+    let pl_time = bench_parking_lot_condvar_wakeup();
+    format_results("post-to-wake latency", std_time, pl_time);
+
+    println!("\nRunning TaskRunner post-to-wake benchmark...");
+    let task_runner_time = bench_task_runner_wakeup();
+    let task_runner_ns = task_runner_time.as_nanos() as f64 / WAKEUPS as f64;
+    println!("\npost-to-wake latency (TaskRunner::post -> block_until_task):");
+    println!("  TaskRunner (parking_lot::Condvar): {task_runner_ns:.2} ns/wakeup");
+
+    // Sanity check the two other entry points an embedder drives a
+    // TaskRunner through: draining without blocking, and waking an idle
+    // thread with no task posted (e.g. to tell it to check shutdown state).
+    let runner = Arc::new(TaskRunner::new());
+    runner.post("a");
+    runner.post("b");
+    let mut drained = Vec::new();
+    runner.run_until_idle(|task| drained.push(task));
+    println!("\nrun_until_idle drained: {drained:?}");
+
+    let runner2 = Arc::clone(&runner);
+    let waiter = thread::spawn(move || runner2.block_until_task(None));
+    thread::sleep(Duration::from_millis(20));
+    runner.wake();
+    let woken = waiter.join().unwrap();
+    println!("wake() unblocked block_until_task with: {woken:?}");
+
+    println!("\n=== Summary ===");
+    println!("parking_lot::Condvar advantages:");
+    println!("  - wait_until/wait_for take a deadline and report whether it elapsed");
+    println!("  - Pairs with parking_lot::Mutex for a single dependency on the hot path");
+    println!("  - Backs TaskRunner::block_until_task so idle threads sleep instead of poll");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_until_task_times_out_when_idle() {
+        let runner = TaskRunner::<u64>::new();
+        let result = runner.block_until_task(Some(Duration::from_millis(10)));
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn block_until_task_returns_posted_task() {
+        let runner = Arc::new(TaskRunner::new());
+
+        let runner2 = Arc::clone(&runner);
+        let poster = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(5));
+            runner2.post(42u64);
+        });
+        let task = runner.block_until_task(Some(Duration::from_secs(5)));
+        poster.join().unwrap();
+        assert_eq!(task, Some(42));
+    }
+
+    #[test]
+    fn run_until_idle_drains_every_queued_task_without_blocking() {
+        let runner = TaskRunner::new();
+        runner.post(1);
+        runner.post(2);
+        runner.post(3);
+
+        let mut seen = Vec::new();
+        runner.run_until_idle(|task| seen.push(task));
+
+        assert_eq!(seen, vec![1, 2, 3]);
+        // The queue is empty, so a further drain does nothing and returns
+        // immediately rather than blocking.
+        runner.run_until_idle(|_| panic!("queue should already be idle"));
+    }
+
+    #[test]
+    fn wake_unblocks_a_waiter_with_no_task() {
+        let runner = Arc::new(TaskRunner::<u64>::new());
+
+        let runner2 = Arc::clone(&runner);
+        let waiter = thread::spawn(move || runner2.block_until_task(None));
+
+        // Give the waiter time to actually block before waking it.
+        thread::sleep(Duration::from_millis(20));
+        runner.wake();
+
+        let result = waiter.join().unwrap();
+        assert!(result.is_none());
+    }
+}